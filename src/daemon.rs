@@ -0,0 +1,213 @@
+//! Rule-driven background daemon for `batterrier watch`.
+//!
+//! Polls power-source and charge-level sysfs nodes and applies the matching
+//! rules from a small INI config, e.g.:
+//! ```ini
+//! [unplug]
+//! trigger = plugged_out
+//! action = set_limit
+//! value = 100
+//!
+//! [hot]
+//! trigger = battery_above
+//! threshold = 90
+//! action = mode
+//! value = force-discharge
+//! ```
+
+use std::{collections::HashMap, fs, path::Path, thread, time::Duration};
+
+use anyhow::{Context, Error, Result};
+use serde::Deserialize;
+
+use crate::{BatteryLimiter, Percent};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const AC_NAMES: [&str; 3] = ["AC", "AC0", "ADP1"];
+
+#[derive(Debug, Deserialize)]
+struct RuleConfig {
+    trigger: String,
+    #[serde(default)]
+    threshold: Option<u8>,
+    action: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trigger {
+    PluggedIn,
+    PluggedOut,
+    BatteryAbove(u8),
+    BatteryBelow(u8),
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    SetLimit {
+        value: Percent,
+        start: Option<Percent>,
+    },
+    Mode(String),
+}
+
+#[derive(Debug)]
+struct Rule {
+    trigger: Trigger,
+    action: Action,
+}
+
+impl TryFrom<RuleConfig> for Rule {
+    type Error = Error;
+
+    fn try_from(config: RuleConfig) -> Result<Self> {
+        let trigger = match config.trigger.as_str() {
+            "plugged_in" => Trigger::PluggedIn,
+            "plugged_out" => Trigger::PluggedOut,
+            "battery_above" => Trigger::BatteryAbove(
+                config
+                    .threshold
+                    .context("'battery_above' rule needs a threshold")?,
+            ),
+            "battery_below" => Trigger::BatteryBelow(
+                config
+                    .threshold
+                    .context("'battery_below' rule needs a threshold")?,
+            ),
+            other => return Err(Error::msg(format!("Unknown trigger '{other}'"))),
+        };
+
+        let action = match config.action.as_str() {
+            "set_limit" => {
+                let mut parts = config.value.split(',');
+                let value = parts
+                    .next()
+                    .context("'set_limit' action needs a value")?
+                    .parse::<Percent>()
+                    .map_err(Error::msg)?;
+                let start = parts
+                    .next()
+                    .map(str::parse::<Percent>)
+                    .transpose()
+                    .map_err(Error::msg)?;
+                Action::SetLimit { value, start }
+            }
+            "mode" => Action::Mode(config.value),
+            other => return Err(Error::msg(format!("Unknown action '{other}'"))),
+        };
+
+        Ok(Self { trigger, action })
+    }
+}
+
+fn load_rules(path: &Path) -> Result<Vec<Rule>> {
+    let contents = fs::read_to_string(path)
+        .context(format!("Failed to read watch config {}", path.display()))?;
+    let sections: HashMap<String, RuleConfig> =
+        serde_ini::from_str(&contents).context("Failed to parse watch config")?;
+    sections.into_values().map(Rule::try_from).collect()
+}
+
+/// Reads `AC*/online`, trying each known AC adapter name in turn.
+fn ac_online() -> Option<bool> {
+    AC_NAMES.iter().find_map(|name| {
+        fs::read_to_string(
+            Path::new("/sys/class/power_supply")
+                .join(name)
+                .join("online"),
+        )
+        .ok()
+        .map(|value| value.trim() == "1")
+    })
+}
+
+fn apply(action: &Action, battery_limiter: &BatteryLimiter) -> Result<()> {
+    match action {
+        Action::SetLimit { value, start } => battery_limiter.set_value(value, start.as_ref()),
+        Action::Mode(mode) => battery_limiter.mode(Some(mode.clone())),
+    }
+}
+
+/// Applies a rule's action, logging (rather than propagating) a failure so
+/// that one misbehaving rule — e.g. a `mode` action on a battery without
+/// `charge_behaviour` — doesn't take the whole daemon down.
+fn apply_logged(action: &Action, battery_limiter: &BatteryLimiter) {
+    if let Err(err) = apply(action, battery_limiter) {
+        eprintln!("Failed to apply watch rule: {err}");
+    }
+}
+
+fn apply_plug_trigger(rules: &[Rule], trigger: Trigger, battery_limiter: &BatteryLimiter) {
+    for rule in rules.iter().filter(|rule| rule.trigger == trigger) {
+        apply_logged(&rule.action, battery_limiter);
+    }
+}
+
+fn level_triggered(trigger: Trigger, capacity: u8) -> bool {
+    match trigger {
+        Trigger::BatteryAbove(threshold) => capacity > threshold,
+        Trigger::BatteryBelow(threshold) => capacity < threshold,
+        Trigger::PluggedIn | Trigger::PluggedOut => false,
+    }
+}
+
+/// Applies every level-triggered rule whose condition has just become true,
+/// i.e. on the crossing rather than on every poll while it holds.
+fn apply_level_triggers(
+    rules: &[Rule],
+    level_state: &mut [bool],
+    capacity: u8,
+    battery_limiter: &BatteryLimiter,
+) {
+    for (rule, was_triggered) in rules.iter().zip(level_state.iter_mut()) {
+        let triggered = level_triggered(rule.trigger, capacity);
+        if triggered && !*was_triggered {
+            apply_logged(&rule.action, battery_limiter);
+        }
+        *was_triggered = triggered;
+    }
+}
+
+/// Runs forever, applying rules on plug/unplug transitions and charge-level
+/// threshold crossings.
+pub fn run(battery_limiter: &BatteryLimiter, config_path: &Path) -> Result<()> {
+    let rules = load_rules(config_path)?;
+    let mut plugged = ac_online();
+    // Tracks, per rule, whether its level condition was already true on the
+    // previous poll, so level rules fire on crossings rather than every poll.
+    let mut level_state = vec![false; rules.len()];
+
+    // Apply rules for the power state and charge level we're already in —
+    // otherwise a daemon started at boot on battery never applies its
+    // "on unplug" rule until the user plugs in and back out again.
+    if let Some(trigger) = plugged.map(|plugged| plug_trigger(plugged)) {
+        apply_plug_trigger(&rules, trigger, battery_limiter);
+    }
+    if let Ok(capacity) = battery_limiter.get_capacity() {
+        apply_level_triggers(&rules, &mut level_state, capacity, battery_limiter);
+    }
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let now_plugged = ac_online();
+        if now_plugged != plugged {
+            if let Some(trigger) = now_plugged.map(plug_trigger) {
+                apply_plug_trigger(&rules, trigger, battery_limiter);
+            }
+            plugged = now_plugged;
+        }
+
+        if let Ok(capacity) = battery_limiter.get_capacity() {
+            apply_level_triggers(&rules, &mut level_state, capacity, battery_limiter);
+        }
+    }
+}
+
+fn plug_trigger(plugged: bool) -> Trigger {
+    if plugged {
+        Trigger::PluggedIn
+    } else {
+        Trigger::PluggedOut
+    }
+}
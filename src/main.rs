@@ -1,11 +1,13 @@
 // src: https://www.linuxuprising.com/2021/02/how-to-limit-battery-charging-set.html
 
+mod daemon;
 mod linux_service;
 
 use anyhow::{Context, Error, Ok, Result};
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
 use regex::Regex;
+use serde::Serialize;
 
 use std::{
     ffi::OsStr,
@@ -16,7 +18,7 @@ use std::{
 
 use linux_service::LinuxService;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct Percent(u8);
 impl std::str::FromStr for Percent {
     type Err = String;
@@ -50,15 +52,56 @@ enum Command {
         #[arg(short, long, default_value_t = false)]
         /// Persist after system reboot, i.e. create a systemd service
         persist: bool,
+        #[arg(long)]
+        /// Battery charge % at which charging resumes [0, 100] (not supported by all drivers)
+        start: Option<Percent>,
+        #[arg(short, long)]
+        /// Only target this battery, e.g. `BAT1` (default: all detected batteries)
+        battery: Option<String>,
         /// Battery charge % limit [0, 100]
         value: Percent,
     },
     /// Print current battery charge limit
-    Get,
+    Get {
+        #[arg(short, long)]
+        /// Only target this battery, e.g. `BAT1` (default: all detected batteries)
+        battery: Option<String>,
+        #[arg(long, default_value_t = false)]
+        /// Emit machine-readable JSON instead of the human-readable text
+        json: bool,
+    },
     /// Restore 100% battery limit and remove systemd service
-    Clean,
+    Clean {
+        #[arg(short, long)]
+        /// Only target this battery, e.g. `BAT1` (default: all detected batteries)
+        battery: Option<String>,
+    },
     /// Print battery info
-    Info,
+    Info {
+        #[arg(short, long)]
+        /// Only target this battery, e.g. `BAT1` (default: all detected batteries)
+        battery: Option<String>,
+        #[arg(long, default_value_t = false)]
+        /// Emit the same machine-readable JSON as `get --json`, instead of the raw sysfs dump
+        json: bool,
+    },
+    /// Get or set the charge behaviour (auto / inhibit-charge / force-discharge)
+    Mode {
+        /// Charge behaviour to switch to; prints available modes if omitted
+        mode: Option<String>,
+        #[arg(short, long)]
+        /// Only target this battery, e.g. `BAT1` (default: all detected batteries)
+        battery: Option<String>,
+    },
+    /// Run as a daemon, adjusting the charge limit/behaviour on plug and battery-level events
+    Watch {
+        #[arg(short, long, default_value = "/etc/batterrier/watch.ini")]
+        /// Path to the rule config file
+        config: PathBuf,
+        #[arg(short, long, default_value_t = false)]
+        /// Persist after system reboot, i.e. create a systemd service that runs the daemon
+        persist: bool,
+    },
     /// Generate shell completions
     #[command(long_about = "Generate shell completions
         Example:
@@ -67,25 +110,308 @@ enum Command {
     Completions { shell: Shell },
 }
 
+/// Machine-readable snapshot of a battery's limits and health, as emitted by
+/// `get --json` and `info --json`.
+#[derive(Serialize)]
+struct BatteryStatus {
+    name: String,
+    current: u8,
+    start_threshold: Option<u8>,
+    persisted: Option<u8>,
+    persisted_start: Option<u8>,
+    status: Option<String>,
+    capacity: Option<u8>,
+    health: Option<f64>,
+}
+
+/// A single `/sys/class/power_supply/BAT?` entry.
+struct Battery {
+    name: String,
+    path: PathBuf,
+    /// Charge behaviours this battery's driver accepts, e.g. `auto`, `inhibit-charge`,
+    /// `force-discharge`. `None` if the driver doesn't expose `charge_behaviour`.
+    charge_behaviours: Option<Vec<String>>,
+}
+impl Battery {
+    fn charge_control_threshold_path(&self) -> PathBuf {
+        self.path.join("charge_control_end_threshold")
+    }
+
+    fn charge_control_start_threshold_path(&self) -> PathBuf {
+        self.path.join("charge_control_start_threshold")
+    }
+
+    fn charge_behaviour_path(&self) -> PathBuf {
+        self.path.join("charge_behaviour")
+    }
+
+    fn read_u64(&self, file: &str) -> Option<u64> {
+        fs::read_to_string(self.path.join(file))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    fn get_value(&self) -> Result<Percent> {
+        fs::read_to_string(self.charge_control_threshold_path())
+            .context(format!(
+                "Failed to read from {}",
+                self.charge_control_threshold_path().display()
+            ))?
+            .trim()
+            .parse::<Percent>()
+            .map_err(|e| Error::msg(format!("Failed to parse battery limit: {e}")))
+    }
+
+    /// Reads the start threshold, if this driver exposes one.
+    fn get_start_value(&self) -> Option<Percent> {
+        fs::read_to_string(self.charge_control_start_threshold_path())
+            .ok()?
+            .trim()
+            .parse::<Percent>()
+            .ok()
+    }
+
+    fn set_value(&self, limit: &Percent, start: Option<&Percent>) -> Result<()> {
+        let Some(start) = start else {
+            return BatteryLimiter::write_protected(
+                self.charge_control_threshold_path(),
+                limit.to_string(),
+            );
+        };
+
+        // Drivers (e.g. ThinkPad's) reject a start threshold that is >= the
+        // currently stored end threshold. So when raising the end we must
+        // write it first to make room for the new start; when lowering it
+        // we must write the start threshold first to make room for the new,
+        // lower end.
+        let raising_end = match self.get_value() {
+            Ok(current_end) => limit.0 >= current_end.0,
+            Err(_) => true,
+        };
+
+        if raising_end {
+            BatteryLimiter::write_protected(
+                self.charge_control_threshold_path(),
+                limit.to_string(),
+            )?;
+            BatteryLimiter::write_protected(
+                self.charge_control_start_threshold_path(),
+                start.to_string(),
+            )
+        } else {
+            BatteryLimiter::write_protected(
+                self.charge_control_start_threshold_path(),
+                start.to_string(),
+            )?;
+            BatteryLimiter::write_protected(
+                self.charge_control_threshold_path(),
+                limit.to_string(),
+            )
+        }
+    }
+
+    fn get_capacity(&self) -> Result<u8> {
+        fs::read_to_string(self.path.join("capacity"))
+            .context("Failed to read battery capacity")?
+            .trim()
+            .parse()
+            .map_err(|e| Error::msg(format!("Failed to parse battery capacity: {e}")))
+    }
+
+    /// Reads the currently active charge behaviour, e.g. `inhibit-charge` from
+    /// `auto [inhibit-charge] force-discharge`.
+    fn get_charge_behaviour(&self) -> Option<String> {
+        let contents = fs::read_to_string(self.charge_behaviour_path()).ok()?;
+        contents
+            .split_whitespace()
+            .find(|mode| mode.starts_with('[') && mode.ends_with(']'))
+            .map(|mode| mode.trim_matches(['[', ']']).to_owned())
+    }
+
+    fn mode(&self, mode: Option<String>) -> Result<()> {
+        let available = self.charge_behaviours.as_ref().ok_or_else(|| {
+            Error::msg(format!("{} doesn't support charge_behaviour", self.name))
+        })?;
+
+        let Some(mode) = mode else {
+            let current = self.get_charge_behaviour();
+            for available_mode in available {
+                let marker = if Some(available_mode) == current.as_ref() {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{marker} {available_mode}");
+            }
+            return Ok(());
+        };
+
+        if !available.contains(&mode) {
+            return Err(Error::msg(format!(
+                "Unknown charge behaviour '{mode}', available: {}",
+                available.join(", ")
+            )));
+        }
+        BatteryLimiter::write_protected(self.charge_behaviour_path(), &mode)?;
+        println!("charge behaviour -> {mode}");
+        Ok(())
+    }
+
+    /// Battery wear, i.e. how much of its design capacity it can still hold.
+    fn health(&self) -> Option<f64> {
+        let full = self.read_u64("energy_full")?;
+        let design = self.read_u64("energy_full_design")?;
+        if design == 0 {
+            return None;
+        }
+        Some(full as f64 / design as f64 * 100.0)
+    }
+
+    /// Estimates time to full (while charging) or time to empty (while discharging),
+    /// formatted as `HH:MM`.
+    fn time_estimate(&self) -> Option<String> {
+        let status = fs::read_to_string(self.path.join("status"))
+            .ok()?
+            .trim()
+            .to_owned();
+        if status == "Full" {
+            return Some("time to full: full".to_owned());
+        }
+
+        let power_now = self.read_u64("power_now")?;
+        let energy_now = self.read_u64("energy_now")?;
+        let energy_full = self.read_u64("energy_full")?;
+
+        let (label, remaining_energy) = match status.as_str() {
+            "Charging" => ("time to full", energy_full.saturating_sub(energy_now)),
+            "Discharging" => ("time to empty", energy_now),
+            _ => return None,
+        };
+
+        if power_now == 0 {
+            return Some(format!("{label}: unknown"));
+        }
+
+        let hours = remaining_energy as f64 / power_now as f64;
+        let total_minutes = (hours * 60.0).round() as u64;
+        Some(format!(
+            "{label}: {:02}:{:02}",
+            total_minutes / 60,
+            total_minutes % 60
+        ))
+    }
+
+    fn info(&self) {
+        let derived = [
+            self.health().map(|health| format!("health: {health:.1}%")),
+            self.time_estimate(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if !derived.is_empty() {
+            println!("{}\n", derived.join("\n"));
+        }
+
+        const INFO_FILES: [&str; 19] = [
+            "alarm",
+            "capacity",
+            "capacity_level",
+            "charge_behaviour",
+            "charge_control_end_threshold",
+            "cycle_count",
+            "energy_full",
+            "energy_full_design",
+            "energy_now",
+            "manufacturer",
+            "model_name",
+            "power_now",
+            "present",
+            "serial_number",
+            "status",
+            "technology",
+            "type",
+            "voltage_min_design",
+            "voltage_now",
+        ];
+
+        let info = INFO_FILES
+            .iter()
+            .filter_map(|file| {
+                fs::read_to_string(self.path.join(file))
+                    .ok()
+                    .map(|value| (file, value.trim().to_owned()))
+            })
+            .collect::<Vec<_>>();
+        let pad_size = info.iter().map(|(file, _)| file.len()).max().unwrap_or(0);
+        let info_string = info
+            .iter()
+            .map(|(file, value)| format!("{file:<pad_size$} {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let info_string = format!("Path: {}\n{info_string}", self.path.display());
+
+        println!("{info_string}");
+    }
+}
+
+/// Manages one or more batteries at once, e.g. to apply the same limit to every
+/// battery on a dual-battery laptop.
 struct BatteryLimiter {
-    bat_path: PathBuf,
+    batteries: Vec<Battery>,
 }
 impl BatteryLimiter {
     const SERVICE_FILENAME: &'static str = "battery-charge-threshold.service";
     const SERVICE_PATH: &'static str =
         const_format::formatcp!("/etc/systemd/system/{}", BatteryLimiter::SERVICE_FILENAME);
+    /// Most drivers resume charging as soon as the battery drops below 100%
+    /// when no start threshold is configured, so this is what `clean` restores.
+    const DEFAULT_START_THRESHOLD: Percent = Percent(0);
 
-    fn new() -> Result<Self> {
+    /// Enumerates every battery under `/sys/class/power_supply` matching one of
+    /// the known names, or just `battery` if given.
+    fn new(battery: Option<&str>) -> Result<Self> {
         // Path to the battery charge limit file is `/sys/class/power_supply/BAT?/charge_control_end_threshold`
         // where  `BAT?` is one of `BAT0`, `BAT1`, `BATT`, `BATC`.
-        const BAT_NAME: [&str; 4] = ["BAT0", "BAT1", "BATT", "BATC"];
-        for bat_name in &BAT_NAME {
-            let bat_path = Path::new("/sys/class/power_supply").join(bat_name);
-            if fs::metadata(&bat_path).is_ok() {
-                return Ok(Self { bat_path });
-            }
+        const BAT_NAMES: [&str; 4] = ["BAT0", "BAT1", "BATT", "BATC"];
+        let batteries = BAT_NAMES
+            .iter()
+            .filter(|bat_name| match battery {
+                Some(filter) => filter == **bat_name,
+                None => true,
+            })
+            .filter_map(|bat_name| {
+                let path = Path::new("/sys/class/power_supply").join(bat_name);
+                fs::metadata(&path).ok()?;
+                Some(Battery {
+                    name: (*bat_name).to_owned(),
+                    charge_behaviours: Self::read_charge_behaviours(&path),
+                    path,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if batteries.is_empty() {
+            return Err(Error::msg(match battery {
+                Some(name) => format!("Battery '{name}' not found"),
+                None => "Battery not found".to_owned(),
+            }));
         }
-        Err(Error::msg("Battery not found".to_owned()))
+        Ok(Self { batteries })
+    }
+
+    /// Parses `charge_behaviour_available`, e.g. `auto [inhibit-charge] force-discharge`,
+    /// into a plain list of mode names. Returns `None` if the driver doesn't expose it.
+    fn read_charge_behaviours(bat_path: &Path) -> Option<Vec<String>> {
+        let contents = fs::read_to_string(bat_path.join("charge_behaviour_available")).ok()?;
+        Some(
+            contents
+                .split_whitespace()
+                .map(|mode| mode.trim_matches(['[', ']']).to_owned())
+                .collect(),
+        )
     }
 
     /// Write to a file with sudo. Equivalent to:
@@ -97,7 +423,7 @@ impl BatteryLimiter {
             .arg(contents)
             .stdout(Stdio::piped())
             .spawn()?;
-        process::Command::new("sudo")
+        let status = process::Command::new("sudo")
             .arg("tee")
             .arg(path.as_ref().as_os_str())
             .stdin(Stdio::from(
@@ -108,6 +434,12 @@ impl BatteryLimiter {
             .stdout(Stdio::null())
             .spawn()?
             .wait()?;
+        if !status.success() {
+            return Err(Error::msg(format!(
+                "Failed to write to {}: sudo tee exited with {status}",
+                path.as_ref().display()
+            )));
+        }
         Ok(())
     }
 
@@ -115,29 +447,26 @@ impl BatteryLimiter {
         println!("🔋{old_limit} -> 🔋{new_limit}");
     }
 
-    fn charge_control_threshold_path(&self) -> PathBuf {
-        self.bat_path.join("charge_control_end_threshold")
-    }
-
-    fn get_value(&self) -> Result<Percent> {
-        fs::read_to_string(self.charge_control_threshold_path())
-            .context(format!(
-                "Failed to read from {}",
-                self.charge_control_threshold_path().display()
-            ))?
-            .trim()
-            .parse::<Percent>()
-            .map_err(|e| Error::msg(format!("Failed to parse battery limit: {e}")))
-    }
-
-    fn set_value(&self, limit: &Percent) -> Result<()> {
-        Self::write_protected(self.charge_control_threshold_path(), limit.to_string())
+    /// Prefixes output with the battery name when more than one is being managed.
+    fn label(&self, battery: &Battery) -> String {
+        if self.batteries.len() > 1 {
+            format!("[{}] ", battery.name)
+        } else {
+            String::new()
+        }
     }
 
-    fn set(&self, limit: &Percent, persist: bool) -> Result<()> {
-        let old_limit = self.get_value()?;
-        self.set_value(limit)?;
-        Self::print_changed_limit(&old_limit, limit);
+    fn set(&self, limit: &Percent, start: Option<&Percent>, persist: bool) -> Result<()> {
+        for battery in &self.batteries {
+            let label = self.label(battery);
+            let old_limit = battery.get_value()?;
+            battery.set_value(limit, start)?;
+            print!("{label}");
+            Self::print_changed_limit(&old_limit, limit);
+            if let Some(start) = start {
+                println!("{label}start threshold -> 🔋{start}");
+            }
+        }
 
         if !persist {
             return Ok(());
@@ -148,11 +477,27 @@ impl BatteryLimiter {
         let mut linux_service: LinuxService =
             serde_ini::from_str(include_str!("../battery-charge-threshold.service")).unwrap();
 
-        linux_service.service.exec_start = format!(
-            "/bin/bash -c 'echo {} > {}'",
-            limit,
-            self.charge_control_threshold_path().display()
-        );
+        let exec_start = self
+            .batteries
+            .iter()
+            .map(|battery| {
+                let mut commands = format!(
+                    "echo {} > {}",
+                    limit,
+                    battery.charge_control_threshold_path().display()
+                );
+                if let Some(start) = start {
+                    commands.push_str(&format!(
+                        "; echo {} > {}",
+                        start,
+                        battery.charge_control_start_threshold_path().display()
+                    ));
+                }
+                commands
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        linux_service.service.exec_start = format!("/bin/bash -c '{exec_start}'");
         let service_contents = serde_ini::to_string(&linux_service)?;
 
         Self::write_protected(BatteryLimiter::SERVICE_PATH, service_contents)?;
@@ -168,37 +513,120 @@ impl BatteryLimiter {
         Ok(())
     }
 
-    fn get_persisted(&self) -> Option<Percent> {
-        let persisted_service: LinuxService =
-            serde_ini::from_str(&fs::read_to_string(Self::SERVICE_PATH).ok()?).ok()?;
-        let re = Regex::new(r"/bin/bash -c 'echo \b(\d+)\b > /sys/class/power_supply/BAT0/charge_control_end_threshold'").unwrap();
-        re.captures(&persisted_service.service.exec_start)?
-            .get(1)?
-            .as_str()
-            .parse()
-            .ok()
+    fn get_persisted_service() -> Option<LinuxService> {
+        serde_ini::from_str(&fs::read_to_string(Self::SERVICE_PATH).ok()?).ok()
     }
 
-    fn get(&self) -> Result<()> {
-        let current_limit = self.get_value()?;
-        let persisted_limit = self.get_persisted();
-        println!("current: 🔋{current_limit}");
+    fn get_persisted_thresholds(
+        battery: &Battery,
+        service: &LinuxService,
+    ) -> Option<(Option<Percent>, Option<Percent>)> {
+        let exec_start = &service.service.exec_start;
+
+        let end_re = Regex::new(&format!(
+            r"echo \b(\d+)\b > /sys/class/power_supply/{}/charge_control_end_threshold",
+            battery.name
+        ))
+        .unwrap();
+        let start_re = Regex::new(&format!(
+            r"echo \b(\d+)\b > /sys/class/power_supply/{}/charge_control_start_threshold",
+            battery.name
+        ))
+        .unwrap();
+
+        let end = end_re
+            .captures(exec_start)
+            .and_then(|c| c.get(1)?.as_str().parse().ok());
+        let start = start_re
+            .captures(exec_start)
+            .and_then(|c| c.get(1)?.as_str().parse().ok());
+
+        if end.is_none() && start.is_none() {
+            return None;
+        }
+        Some((end, start))
+    }
+
+    /// Snapshots everything `get`/`info --json` report about one battery.
+    fn status(
+        &self,
+        battery: &Battery,
+        persisted_service: Option<&LinuxService>,
+    ) -> Result<BatteryStatus> {
+        let current = battery.get_value()?;
+        let start_threshold = battery.get_start_value();
+        let (persisted, persisted_start) = persisted_service
+            .and_then(|service| Self::get_persisted_thresholds(battery, service))
+            .unwrap_or_default();
+
+        Ok(BatteryStatus {
+            name: battery.name.clone(),
+            current: current.0,
+            start_threshold: start_threshold.map(|p| p.0),
+            persisted: persisted.map(|p| p.0),
+            persisted_start: persisted_start.map(|p| p.0),
+            status: fs::read_to_string(battery.path.join("status"))
+                .ok()
+                .map(|s| s.trim().to_owned()),
+            capacity: battery.get_capacity().ok(),
+            health: battery.health(),
+        })
+    }
+
+    fn statuses(&self) -> Result<Vec<BatteryStatus>> {
+        let persisted_service = Self::get_persisted_service();
+        self.batteries
+            .iter()
+            .map(|battery| self.status(battery, persisted_service.as_ref()))
+            .collect()
+    }
+
+    fn print_status(&self, battery: &Battery, status: &BatteryStatus) {
+        let label = self.label(battery);
+        println!("{label}current: 🔋{}", status.current);
+        if let Some(start_threshold) = status.start_threshold {
+            println!("{label}current start: 🔋{start_threshold}");
+        }
         println!(
-            "persisted: {}",
-            if let Some(persisted_limit) = persisted_limit {
-                format!("🔋{persisted_limit}")
+            "{label}persisted: {}",
+            if let Some(persisted) = status.persisted {
+                format!("🔋{persisted}")
             } else {
                 "Not set".to_owned()
             }
         );
+        if let Some(persisted_start) = status.persisted_start {
+            println!("{label}persisted start: 🔋{persisted_start}");
+        }
+    }
+
+    fn get(&self, json: bool) -> Result<()> {
+        let statuses = self.statuses()?;
+
+        if json {
+            println!("{}", serde_json::to_string(&statuses)?);
+            return Ok(());
+        }
+
+        for (battery, status) in self.batteries.iter().zip(&statuses) {
+            self.print_status(battery, status);
+        }
 
         Ok(())
     }
 
     fn clean(&self) -> Result<()> {
-        let old_limit = self.get_value()?;
-        self.set_value(&Percent(100))?;
-        Self::print_changed_limit(&old_limit, &Percent(100));
+        for battery in &self.batteries {
+            let label = self.label(battery);
+            let old_limit = battery.get_value()?;
+            let start = battery
+                .get_start_value()
+                .is_some()
+                .then_some(&Self::DEFAULT_START_THRESHOLD);
+            battery.set_value(&Percent(100), start)?;
+            print!("{label}");
+            Self::print_changed_limit(&old_limit, &Percent(100));
+        }
 
         if fs::metadata(BatteryLimiter::SERVICE_PATH).is_ok() {
             println!("Removing systemd service");
@@ -212,63 +640,122 @@ impl BatteryLimiter {
         Ok(())
     }
 
-    fn info(&self) {
-        const INFO_FILES: [&str; 18] = [
-            "alarm",
-            "capacity",
-            "capacity_level",
-            "charge_control_end_threshold",
-            "cycle_count",
-            "energy_full",
-            "energy_full_design",
-            "energy_now",
-            "manufacturer",
-            "model_name",
-            "power_now",
-            "present",
-            "serial_number",
-            "status",
-            "technology",
-            "type",
-            "voltage_min_design",
-            "voltage_now",
-        ];
+    /// Installs and starts a systemd service that runs `batterrier watch` in the
+    /// background, instead of the one-shot threshold echo used by `set --persist`.
+    fn persist_watch(&self, config_path: &Path) -> Result<()> {
+        println!("Creating systemd service");
 
-        let info = INFO_FILES
-            .iter()
-            .filter_map(|file| {
-                fs::read_to_string(self.bat_path.join(file))
-                    .ok()
-                    .map(|value| (file, value.trim().to_owned()))
-            })
-            .collect::<Vec<_>>();
-        let pad_size = info.iter().map(|(file, _)| file.len()).max().unwrap_or(0);
-        let info_string = info
-            .iter()
-            .map(|(file, value)| format!("{file:<pad_size$} {value}"))
-            .collect::<Vec<_>>()
-            .join("\n");
-        let info_string = format!("Path: {}\n{info_string}", self.bat_path.display());
+        let mut linux_service: LinuxService =
+            serde_ini::from_str(include_str!("../battery-charge-threshold.service")).unwrap();
 
-        println!("{info_string}");
+        linux_service.service.exec_start = format!(
+            "{} watch --config {}",
+            std::env::current_exe()?.display(),
+            config_path.display()
+        );
+        linux_service.service.restart = Some("on-failure".to_owned());
+        let service_contents = serde_ini::to_string(&linux_service)?;
+
+        Self::write_protected(BatteryLimiter::SERVICE_PATH, service_contents)?;
+
+        process::Command::new("sudo")
+            .args(
+                const_format::formatcp!(
+                    "systemctl enable --now {}",
+                    BatteryLimiter::SERVICE_FILENAME
+                )
+                .split(' '),
+            )
+            .spawn()?
+            .wait()?;
+
+        Ok(())
+    }
+
+    fn info(&self, json: bool) -> Result<()> {
+        if json {
+            // `info --json` intentionally mirrors `get --json`: scripts want
+            // the same stable fields every time, not an ad hoc dump of
+            // whatever raw sysfs files happen to exist.
+            return self.get(true);
+        }
+
+        for battery in &self.batteries {
+            if self.batteries.len() > 1 {
+                println!("=== {} ===", battery.name);
+            }
+            battery.info();
+        }
+
+        Ok(())
+    }
+
+    fn mode(&self, mode: Option<String>) -> Result<()> {
+        for battery in &self.batteries {
+            if self.batteries.len() > 1 {
+                println!("=== {} ===", battery.name);
+            }
+            battery.mode(mode.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Applies a threshold to every managed battery. Used by the watch daemon,
+    /// which always targets every detected battery.
+    fn set_value(&self, limit: &Percent, start: Option<&Percent>) -> Result<()> {
+        for battery in &self.batteries {
+            battery.set_value(limit, start)?;
+        }
+        Ok(())
+    }
+
+    /// Capacity of the first managed battery, used by the watch daemon's
+    /// charge-level triggers.
+    fn get_capacity(&self) -> Result<u8> {
+        self.batteries
+            .first()
+            .context("No battery found")?
+            .get_capacity()
     }
 }
 
 fn main() -> Result<()> {
     let args = Cli::parse();
-    let battery_limiter = BatteryLimiter::new()?;
 
     match args.command {
-        Command::Set { persist, value } => {
-            battery_limiter.set(&value, persist)?;
+        Command::Set {
+            persist,
+            start,
+            battery,
+            value,
+        } => {
+            let battery_limiter = BatteryLimiter::new(battery.as_deref())?;
+            battery_limiter.set(&value, start.as_ref(), persist)?;
         }
-        Command::Get => {
-            battery_limiter.get()?;
+        Command::Get { battery, json } => {
+            let battery_limiter = BatteryLimiter::new(battery.as_deref())?;
+            battery_limiter.get(json)?;
         }
-        Command::Clean => {
+        Command::Clean { battery } => {
+            let battery_limiter = BatteryLimiter::new(battery.as_deref())?;
             battery_limiter.clean()?;
         }
-        Command::Info => battery_limiter.info(),
+        Command::Info { battery, json } => {
+            let battery_limiter = BatteryLimiter::new(battery.as_deref())?;
+            battery_limiter.info(json)?;
+        }
+        Command::Mode { mode, battery } => {
+            let battery_limiter = BatteryLimiter::new(battery.as_deref())?;
+            battery_limiter.mode(mode)?;
+        }
+        Command::Watch { config, persist } => {
+            let battery_limiter = BatteryLimiter::new(None)?;
+            if persist {
+                battery_limiter.persist_watch(&config)?;
+            } else {
+                daemon::run(&battery_limiter, &config)?;
+            }
+        }
         Command::Completions { shell } => {
             clap_complete::generate(
                 shell,